@@ -1,5 +1,4 @@
 use std::io::{Read, BufRead, Cursor, Seek, SeekFrom, Error, ErrorKind};
-use std::mem::{transmute, size_of};
 use std::collections::HashMap;
 
 
@@ -25,28 +24,6 @@ macro_rules! parse_u8 {
     }};
 }
 
-macro_rules! parse_u16 {
-    ($buff: ident) => {{
-        let mut val: [u8; 2] = [0; 2];
-        $buff.read_exact(&mut val)?;
-        unsafe { u8_to_u16(val) }
-    }};
-}
-
-macro_rules! parse_u32 {
-    ($buff: ident) => {{
-        let mut val: [u8; 4] = [0; 4];
-        $buff.read_exact(&mut val)?;
-        unsafe { u8_to_u32(val) }
-    }};
-}
-
-macro_rules! parse_i16 {
-    ($buff: ident) => {{
-        parse_u16!($buff) as i16
-    }};
-}
-
 macro_rules! parse_string {
     ($buff: ident) => {{
         let mut string = Vec::new();
@@ -64,14 +41,102 @@ macro_rules! parse_string {
 }
 
 
-#[inline]
-unsafe fn u8_to_u32(a: [u8; 4]) -> u32 {
-    transmute::<[u8; 4], u32>(a)
+/// Typed little-endian accessors for anything that implements `Read`.
+///
+/// The BMFont binary format is defined as little-endian regardless of host
+/// platform, so every multi-byte field is decoded explicitly here rather
+/// than via a native-order transmute.
+trait BinRead: Read {
+    fn read_u16_le(&mut self) -> Result<u16, Error> {
+        let mut val: [u8; 2] = [0; 2];
+        self.read_exact(&mut val)?;
+        Ok(u16::from_le_bytes(val))
+    }
+
+    fn read_i16_le(&mut self) -> Result<i16, Error> {
+        let mut val: [u8; 2] = [0; 2];
+        self.read_exact(&mut val)?;
+        Ok(i16::from_le_bytes(val))
+    }
+
+    fn read_u32_le(&mut self) -> Result<u32, Error> {
+        let mut val: [u8; 4] = [0; 4];
+        self.read_exact(&mut val)?;
+        Ok(u32::from_le_bytes(val))
+    }
+
+    fn try_read_u32_le(&mut self) -> Option<u32> {
+        self.read_u32_le().ok()
+    }
+}
+
+impl<R: Read + ?Sized> BinRead for R {}
+
+
+/// Typed little-endian writers, mirroring `BinRead`.
+trait BinWrite {
+    fn write_u16_le(&mut self, v: u16);
+    fn write_i16_le(&mut self, v: i16);
+    fn write_u32_le(&mut self, v: u32);
+    fn write_cstring(&mut self, s: &str);
+}
+
+impl BinWrite for Vec<u8> {
+    fn write_u16_le(&mut self, v: u16) {
+        self.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn write_i16_le(&mut self, v: i16) {
+        self.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn write_u32_le(&mut self, v: u32) {
+        self.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn write_cstring(&mut self, s: &str) {
+        self.extend_from_slice(s.as_bytes());
+        self.push(0);
+    }
+}
+
+/// Write a type byte and little-endian `u32` size ahead of `content`, as
+/// every block in a version-3 BMF file does.
+fn write_block(out: &mut Vec<u8>, block_type: u8, content: &[u8]) {
+    out.push(block_type);
+    out.write_u32_le(content.len() as u32);
+    out.extend_from_slice(content);
 }
 
-#[inline]
-unsafe fn u8_to_u16(a: [u8; 2]) -> u16 {
-    transmute::<[u8; 2], u16>(a)
+
+// On-the-wire record sizes for the Chars and Kernings blocks. These are
+// spelled out explicitly rather than taken from `size_of::<Char>()` /
+// `size_of::<Kerning>()`, since Rust is free to pad a struct's in-memory
+// layout up to its field alignment (e.g. `Kerning` is 10 bytes on the wire
+// but would size to 12 in memory), which does not match the packed layout
+// the BMFont format actually uses.
+const CHAR_RECORD_SIZE: usize = 20;
+const KERNING_RECORD_SIZE: usize = 10;
+
+/// Validate a block's declared byte `size` against the bytes actually left
+/// in the buffer and the fixed `record_size` of its element type, returning
+/// the element count to allocate/read. Guards against corrupt or hostile
+/// files advertising a huge `size` that would otherwise trigger a massive
+/// `Vec::with_capacity` before any bytes are read.
+fn checked_record_count(what: &str, size: u32, record_size: usize, remaining: u64) -> Result<usize, Error> {
+    if u64::from(size) > remaining {
+        parse_error!(format!("{} block size exceeds remaining input", what));
+    }
+
+    let size = size as usize;
+    if !size.is_multiple_of(record_size) {
+        parse_error!(format!("{} block size is not a multiple of its record size", what));
+    }
+
+    match size.checked_div(record_size) {
+        Some(count) => Ok(count),
+        None => parse_error!(format!("{} record size is zero", what)),
+    }
 }
 
 
@@ -89,7 +154,7 @@ fn parse_bin(bytes: &[u8]) -> Result<BMFont, Error> {
     buff.seek(SeekFrom::Current(5))?;
 
     // Begin Info block.
-    let font_size = parse_i16!(buff);
+    let font_size = buff.read_i16_le()?;
     let bit_field = parse_u8!(buff);
 
     let block_info = Info {
@@ -100,7 +165,7 @@ fn parse_bin(bytes: &[u8]) -> Result<BMFont, Error> {
         bold          : bit_field & (1 << 4) != 0,
         fixed_height  : bit_field & (1 << 3) != 0,
         charset       : parse_u8!(buff),
-        stretch_h     : parse_u16!(buff),
+        stretch_h     : buff.read_u16_le()?,
         aa            : parse_u8!(buff),
         padding_up    : parse_u8!(buff),
         padding_right : parse_u8!(buff),
@@ -117,11 +182,11 @@ fn parse_bin(bytes: &[u8]) -> Result<BMFont, Error> {
 
     // Begin Common block.
     let block_common = Common {
-        line_height : parse_u16!(buff),
-        base        : parse_u16!(buff),
-        scale_w     : parse_u16!(buff),
-        scale_h     : parse_u16!(buff),
-        pages       : parse_u16!(buff),
+        line_height : buff.read_u16_le()?,
+        base        : buff.read_u16_le()?,
+        scale_w     : buff.read_u16_le()?,
+        scale_h     : buff.read_u16_le()?,
+        pages       : buff.read_u16_le()?,
         packed      : parse_u8!(buff) & 1 != 0,
         alpha_chnl  : parse_u8!(buff),
         red_chnl    : parse_u8!(buff),
@@ -142,49 +207,47 @@ fn parse_bin(bytes: &[u8]) -> Result<BMFont, Error> {
     buff.seek(SeekFrom::Current(1))?;
 
     // Chars block size.
-    let size = parse_u32!(buff);
+    let size = buff.read_u32_le()?;
 
-    let total_chars = size / size_of::<Char>() as u32;
-    let mut block_chars = Vec::with_capacity(total_chars as usize);
+    let remaining = bytes.len() as u64 - buff.position();
+    let total_chars = checked_record_count("Chars", size, CHAR_RECORD_SIZE, remaining)?;
+    let mut block_chars = Vec::with_capacity(total_chars);
 
     for _ in 0..total_chars {
         block_chars.push(Char {
-            id       : parse_u32!(buff),
-            x        : parse_u16!(buff),
-            y        : parse_u16!(buff),
-            width    : parse_u16!(buff),
-            height   : parse_u16!(buff),
-            xoffset  : parse_i16!(buff),
-            yoffset  : parse_i16!(buff),
-            xadvance : parse_i16!(buff),
+            id       : buff.read_u32_le()?,
+            x        : buff.read_u16_le()?,
+            y        : buff.read_u16_le()?,
+            width    : buff.read_u16_le()?,
+            height   : buff.read_u16_le()?,
+            xoffset  : buff.read_i16_le()?,
+            yoffset  : buff.read_i16_le()?,
+            xadvance : buff.read_i16_le()?,
             page     : parse_u8!(buff),
             chnl     : parse_u8!(buff),
         });
     }
 
-    // Check Kerning block exists.
-    let block_kernings = if buff.position() < bytes.len() as u64 {
-
-        // Skip block type.
-        buff.seek(SeekFrom::Current(1))?;
-
-        // Chars block size.
-        let size = parse_u32!(buff);
-
-        let total_pairs = size / size_of::<Kerning>() as u32;
-        let mut pairs_list = Vec::with_capacity(total_pairs as usize);
-
-        for _ in 0..total_pairs {
-            pairs_list.push(Kerning {
-                first  : parse_u32!(buff),
-                second : parse_u32!(buff),
-                amount : parse_i16!(buff),
-            });
+    // Skip block type, then probe for a Kernings block: if there aren't
+    // enough bytes left for its size field, there is no kernings block.
+    buff.seek(SeekFrom::Current(1))?;
+    let block_kernings = match buff.try_read_u32_le() {
+        Some(size) => {
+            let remaining = bytes.len() as u64 - buff.position();
+            let total_pairs = checked_record_count("Kernings", size, KERNING_RECORD_SIZE, remaining)?;
+            let mut pairs_list = Vec::with_capacity(total_pairs);
+
+            for _ in 0..total_pairs {
+                pairs_list.push(Kerning {
+                    first  : buff.read_u32_le()?,
+                    second : buff.read_u32_le()?,
+                    amount : buff.read_i16_le()?,
+                });
+            }
+
+            Some(pairs_list)
         }
-
-        Some(pairs_list)
-    } else {
-        None
+        None => None,
     };
 
     // HashMap by character id.
@@ -198,18 +261,301 @@ fn parse_bin(bytes: &[u8]) -> Result<BMFont, Error> {
         common: block_common,
         pages: block_pages,
         chars: char_map,
+        kerning_pairs: kerning_map(&block_kernings),
         kernings: block_kernings,
     })
 }
 
+/// Index kerning pairs by `(first, second)` id so `BMFont::kerning` can look
+/// up an adjustment without scanning the whole `kernings` list.
+fn kerning_map(kernings: &Option<Vec<Kerning>>) -> HashMap<(u32, u32), i16> {
+    let kernings = match kernings {
+        Some(kernings) => kernings,
+        None => return HashMap::new(),
+    };
+
+    let mut map = HashMap::with_capacity(kernings.len());
+    for k in kernings {
+        map.insert((k.first, k.second), k.amount);
+    }
+    map
+}
+
+
+/// Advance `chars` past a run of codepoints matching `pred`.
+fn skip_while(chars: &mut std::iter::Peekable<std::str::CharIndices<'_>>, pred: impl Fn(char) -> bool) {
+    while chars.peek().is_some_and(|&(_, c)| pred(c)) {
+        chars.next();
+    }
+}
+
+/// Advance `chars` past a run of codepoints matching `pred`, returning the
+/// byte offset just past the run (or `len` if the run reaches the end).
+fn scan_while(chars: &mut std::iter::Peekable<std::str::CharIndices<'_>>, pred: impl Fn(char) -> bool, len: usize) -> usize {
+    loop {
+        match chars.peek() {
+            Some(&(_, c)) if pred(c) => { chars.next(); }
+            Some(&(i, _)) => return i,
+            None => return len,
+        }
+    }
+}
+
+/// Split a `key=value key="quoted value"` fragment, as used by both the
+/// text descriptor and the attributes of an XML element, into a map.
+/// Unquoted values run to the next whitespace; quoted values may contain
+/// whitespace.
+fn tokenize_attrs(s: &str) -> HashMap<String, String> {
+    let mut attrs = HashMap::new();
+    let mut chars = s.char_indices().peekable();
+
+    loop {
+        skip_while(&mut chars, char::is_whitespace);
+
+        let key_start = match chars.peek() {
+            Some(&(i, _)) => i,
+            None => break,
+        };
+        let key_end = scan_while(&mut chars, |c| c != '=' && !c.is_whitespace(), s.len());
+        let key = &s[key_start..key_end];
+        if key.is_empty() {
+            break;
+        }
+
+        skip_while(&mut chars, char::is_whitespace);
+        if chars.peek().map(|&(_, c)| c) != Some('=') {
+            continue;
+        }
+        chars.next();
+        skip_while(&mut chars, char::is_whitespace);
+
+        let value = if chars.peek().map(|&(_, c)| c) == Some('"') {
+            chars.next();
+            let value_start = chars.peek().map_or(s.len(), |&(i, _)| i);
+            let value_end = scan_while(&mut chars, |c| c != '"', s.len());
+            chars.next();
+            &s[value_start..value_end]
+        } else {
+            let value_start = chars.peek().map_or(s.len(), |&(i, _)| i);
+            let value_end = scan_while(&mut chars, |c| !c.is_whitespace(), s.len());
+            &s[value_start..value_end]
+        };
+
+        attrs.insert(key.to_string(), value.to_string());
+    }
+
+    attrs
+}
+
+fn attr<T: std::str::FromStr>(attrs: &HashMap<String, String>, key: &str) -> Option<T> {
+    attrs.get(key).and_then(|v| v.parse().ok())
+}
+
+fn attr_or<T: std::str::FromStr>(attrs: &HashMap<String, String>, key: &str, default: T) -> T {
+    attr(attrs, key).unwrap_or(default)
+}
+
+fn attr_bool(attrs: &HashMap<String, String>, key: &str) -> bool {
+    attr::<u8>(attrs, key).map(|v| v != 0).unwrap_or(false)
+}
+
+fn attr_string(attrs: &HashMap<String, String>, key: &str) -> String {
+    attrs.get(key).cloned().unwrap_or_default()
+}
+
+fn attr_csv<T: std::str::FromStr + Default>(attrs: &HashMap<String, String>, key: &str) -> Vec<T> {
+    attrs.get(key)
+        .map(|v| v.split(',').map(|p| p.trim().parse().unwrap_or_default()).collect())
+        .unwrap_or_default()
+}
+
+fn info_from_attrs(attrs: &HashMap<String, String>) -> Info {
+    let padding: Vec<u8> = attr_csv(attrs, "padding");
+    let spacing: Vec<u8> = attr_csv(attrs, "spacing");
+
+    Info {
+        font_size: attr_or(attrs, "size", 0),
+        smooth: attr_bool(attrs, "smooth"),
+        unicode: attr_bool(attrs, "unicode"),
+        italic: attr_bool(attrs, "italic"),
+        bold: attr_bool(attrs, "bold"),
+        fixed_height: attr_bool(attrs, "fixedHeight"),
+        // The text/XML formats store the charset as its human-readable name
+        // (e.g. "ANSI", or empty for the default); the binary format stores
+        // the raw byte instead. Without a name->id table we can only carry
+        // numeric charsets through losslessly.
+        charset: attr_or(attrs, "charset", 0),
+        stretch_h: attr_or(attrs, "stretchH", 0),
+        aa: attr_or(attrs, "aa", 0),
+        padding_up: *padding.first().unwrap_or(&0),
+        padding_right: *padding.get(1).unwrap_or(&0),
+        padding_down: *padding.get(2).unwrap_or(&0),
+        padding_left: *padding.get(3).unwrap_or(&0),
+        spacing_horiz: *spacing.first().unwrap_or(&0),
+        spacing_vert: *spacing.get(1).unwrap_or(&0),
+        outline: attr_or(attrs, "outline", 0),
+        font_name: attr_string(attrs, "face"),
+    }
+}
+
+fn common_from_attrs(attrs: &HashMap<String, String>) -> Common {
+    Common {
+        line_height: attr_or(attrs, "lineHeight", 0),
+        base: attr_or(attrs, "base", 0),
+        scale_w: attr_or(attrs, "scaleW", 0),
+        scale_h: attr_or(attrs, "scaleH", 0),
+        pages: attr_or(attrs, "pages", 0),
+        packed: attr_bool(attrs, "packed"),
+        alpha_chnl: attr_or(attrs, "alphaChnl", 0),
+        red_chnl: attr_or(attrs, "redChnl", 0),
+        green_chnl: attr_or(attrs, "greenChnl", 0),
+        blue_chnl: attr_or(attrs, "blueChnl", 0),
+    }
+}
+
+fn char_from_attrs(attrs: &HashMap<String, String>) -> Char {
+    Char {
+        id: attr_or(attrs, "id", 0),
+        x: attr_or(attrs, "x", 0),
+        y: attr_or(attrs, "y", 0),
+        width: attr_or(attrs, "width", 0),
+        height: attr_or(attrs, "height", 0),
+        xoffset: attr_or(attrs, "xoffset", 0),
+        yoffset: attr_or(attrs, "yoffset", 0),
+        xadvance: attr_or(attrs, "xadvance", 0),
+        page: attr_or(attrs, "page", 0),
+        chnl: attr_or(attrs, "chnl", 0),
+    }
+}
+
+fn kerning_from_attrs(attrs: &HashMap<String, String>) -> Kerning {
+    Kerning {
+        first: attr_or(attrs, "first", 0),
+        second: attr_or(attrs, "second", 0),
+        amount: attr_or(attrs, "amount", 0),
+    }
+}
+
+/// Order `page` entries by their declared `id` rather than the order they
+/// appear in the file, since `Char::page` indexes into this list by id.
+fn assemble_pages(mut entries: Vec<(u32, String)>) -> Vec<String> {
+    entries.sort_by_key(|&(id, _)| id);
+    entries.into_iter().map(|(_, file)| file).collect()
+}
+
+/// Accumulates the blocks common to the text and XML descriptor formats
+/// as their tags are encountered, then assembles a `BMFont` at the end.
+#[derive(Default)]
+struct BlockAccumulator {
+    info: Option<Info>,
+    common: Option<Common>,
+    pages: Vec<(u32, String)>,
+    chars: Vec<Char>,
+    kernings: Vec<Kerning>,
+    has_kernings_block: bool,
+}
+
+impl BlockAccumulator {
+    fn apply_tag(&mut self, name: &str, attrs: &HashMap<String, String>) {
+        match name {
+            "info" => self.info = Some(info_from_attrs(attrs)),
+            "common" => self.common = Some(common_from_attrs(attrs)),
+            "page" => self.pages.push((attr_or(attrs, "id", 0), attr_string(attrs, "file"))),
+            "char" => self.chars.push(char_from_attrs(attrs)),
+            "kerning" => self.kernings.push(kerning_from_attrs(attrs)),
+            "kernings" => self.has_kernings_block = true,
+            _ => {}
+        }
+    }
+
+    fn finish(self) -> Result<BMFont, Error> {
+        let info = match self.info {
+            Some(info) => info,
+            None => parse_error!("Missing info block"),
+        };
+        let common = match self.common {
+            Some(common) => common,
+            None => parse_error!("Missing common block"),
+        };
+
+        let mut chars = HashMap::with_capacity(self.chars.len());
+        for c in self.chars {
+            chars.insert(c.id, c);
+        }
+
+        let kernings = if self.has_kernings_block { Some(self.kernings) } else { None };
+
+        Ok(BMFont {
+            info,
+            common,
+            pages: assemble_pages(self.pages),
+            chars,
+            kerning_pairs: kerning_map(&kernings),
+            kernings,
+        })
+    }
+}
+
+fn parse_text(text: &str) -> Result<BMFont, Error> {
+    let mut blocks = BlockAccumulator::default();
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
 
-#[derive(Debug)]
+        let (tag, rest) = match line.find(char::is_whitespace) {
+            Some(idx) => (&line[..idx], &line[idx..]),
+            None => (line, ""),
+        };
+
+        blocks.apply_tag(tag, &tokenize_attrs(rest));
+    }
+
+    blocks.finish()
+}
+
+fn parse_xml(text: &str) -> Result<BMFont, Error> {
+    let mut blocks = BlockAccumulator::default();
+    let mut rest = text;
+
+    while let Some(start) = rest.find('<') {
+        let after = &rest[start + 1..];
+        let end = match after.find('>') {
+            Some(end) => end,
+            None => break,
+        };
+
+        let tag_content = &after[..end];
+        rest = &after[end + 1..];
+
+        // Skip the XML declaration, comments and closing tags.
+        if tag_content.starts_with('?') || tag_content.starts_with('!') || tag_content.starts_with('/') {
+            continue;
+        }
+
+        let tag_content = tag_content.trim_end_matches('/').trim_end();
+        let (name, attr_str) = match tag_content.find(char::is_whitespace) {
+            Some(idx) => (&tag_content[..idx], &tag_content[idx..]),
+            None => (tag_content, ""),
+        };
+
+        blocks.apply_tag(name, &tokenize_attrs(attr_str));
+    }
+
+    blocks.finish()
+}
+
+
+#[derive(Debug, PartialEq)]
 pub struct BMFont {
     pub info: Info,
     pub common: Common,
     pub pages: Vec<String>,
     pub chars: HashMap<u32, Char>,
-    pub kernings: Option<Vec<Kerning>>
+    pub kernings: Option<Vec<Kerning>>,
+    kerning_pairs: HashMap<(u32, u32), i16>,
 }
 
 
@@ -218,17 +564,215 @@ impl BMFont {
         parse_bin(bytes)
     }
 
-    pub fn str_to_chars<'a>(&'a self, s: &str) -> Vec<&'a Char> {
-        String::from(s)
-            .into_bytes()
-            .iter()
-            .map(|&b| &self.chars[&u32::from(b)])
+    /// Parse the plain-text `key=value` descriptor format.
+    pub fn from_text(text: &str) -> Result<BMFont, Error> {
+        parse_text(text)
+    }
+
+    /// Parse the XML descriptor format.
+    pub fn from_xml(text: &str) -> Result<BMFont, Error> {
+        parse_xml(text)
+    }
+
+    /// Detect and parse whichever of the three BMFont descriptor formats
+    /// (binary, text or XML) `bytes` holds.
+    pub fn parse(bytes: &[u8]) -> Result<BMFont, Error> {
+        if bytes.starts_with(b"BMF") {
+            return parse_bin(bytes);
+        }
+
+        let text = match std::str::from_utf8(bytes) {
+            Ok(text) => text,
+            Err(err) => parse_error!(err),
+        };
+
+        match text.split_whitespace().next() {
+            Some("<?xml") => parse_xml(text),
+            Some(tag) if tag.starts_with("<font") => parse_xml(text),
+            Some("info") => parse_text(text),
+            _ => parse_error!("Unrecognized BMFont format"),
+        }
+    }
+
+    /// Look up each codepoint of `s` as a glyph id, in order. A codepoint
+    /// with no matching glyph yields `None` rather than panicking.
+    pub fn str_to_chars<'a>(&'a self, s: &str) -> Vec<Option<&'a Char>> {
+        s.chars()
+            .map(|c| self.chars.get(&(c as u32)))
             .collect()
     }
+
+    /// The kerning adjustment between two adjacent glyph ids, or `0` if the
+    /// pair has no entry in the Kernings block.
+    pub fn kerning(&self, first: u32, second: u32) -> i16 {
+        self.kerning_pairs.get(&(first, second)).copied().unwrap_or(0)
+    }
+
+    /// Sum `xadvance` plus kerning across `s`, as if laying the string out
+    /// left to right. Codepoints with no matching glyph contribute nothing.
+    pub fn layout_width(&self, s: &str) -> i32 {
+        let mut width = 0i32;
+        let mut prev_id: Option<u32> = None;
+
+        for c in s.chars() {
+            let id = c as u32;
+
+            if let Some(prev_id) = prev_id {
+                width += i32::from(self.kerning(prev_id, id));
+            }
+
+            if let Some(glyph) = self.chars.get(&id) {
+                width += i32::from(glyph.xadvance);
+            }
+
+            prev_id = Some(id);
+        }
+
+        width
+    }
+
+    /// Serialize to a version-3 binary BMF file, the inverse of `BMFont::new`.
+    pub fn to_bin(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(b"BMF\x03");
+
+        let mut info = Vec::new();
+        info.write_i16_le(self.info.font_size);
+        let bit_field = (self.info.smooth as u8) << 7
+            | (self.info.unicode as u8) << 6
+            | (self.info.italic as u8) << 5
+            | (self.info.bold as u8) << 4
+            | (self.info.fixed_height as u8) << 3;
+        info.push(bit_field);
+        info.push(self.info.charset);
+        info.write_u16_le(self.info.stretch_h);
+        info.push(self.info.aa);
+        info.push(self.info.padding_up);
+        info.push(self.info.padding_right);
+        info.push(self.info.padding_down);
+        info.push(self.info.padding_left);
+        info.push(self.info.spacing_horiz);
+        info.push(self.info.spacing_vert);
+        info.push(self.info.outline);
+        info.write_cstring(&self.info.font_name);
+        write_block(&mut out, 1, &info);
+
+        let mut common = Vec::new();
+        common.write_u16_le(self.common.line_height);
+        common.write_u16_le(self.common.base);
+        common.write_u16_le(self.common.scale_w);
+        common.write_u16_le(self.common.scale_h);
+        common.write_u16_le(self.common.pages);
+        common.push(self.common.packed as u8);
+        common.push(self.common.alpha_chnl);
+        common.push(self.common.red_chnl);
+        common.push(self.common.green_chnl);
+        common.push(self.common.blue_chnl);
+        write_block(&mut out, 2, &common);
+
+        let mut pages = Vec::new();
+        for page in &self.pages {
+            pages.write_cstring(page);
+        }
+        write_block(&mut out, 3, &pages);
+
+        let mut char_ids: Vec<&u32> = self.chars.keys().collect();
+        char_ids.sort();
+
+        let mut chars = Vec::new();
+        for id in char_ids {
+            let c = &self.chars[id];
+            chars.write_u32_le(c.id);
+            chars.write_u16_le(c.x);
+            chars.write_u16_le(c.y);
+            chars.write_u16_le(c.width);
+            chars.write_u16_le(c.height);
+            chars.write_i16_le(c.xoffset);
+            chars.write_i16_le(c.yoffset);
+            chars.write_i16_le(c.xadvance);
+            chars.push(c.page);
+            chars.push(c.chnl);
+        }
+        write_block(&mut out, 4, &chars);
+
+        if let Some(kernings) = &self.kernings {
+            let mut block = Vec::new();
+            for k in kernings {
+                block.write_u32_le(k.first);
+                block.write_u32_le(k.second);
+                block.write_i16_le(k.amount);
+            }
+            write_block(&mut out, 5, &block);
+        }
+
+        out
+    }
+
+    /// Serialize to the plain-text `key=value` descriptor format, the
+    /// inverse of `BMFont::from_text`.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str(&format!(
+            "info face=\"{}\" size={} bold={} italic={} charset={} unicode={} stretchH={} smooth={} aa={} padding={},{},{},{} spacing={},{} outline={} fixedHeight={}\n",
+            self.info.font_name,
+            self.info.font_size,
+            self.info.bold as u8,
+            self.info.italic as u8,
+            self.info.charset,
+            self.info.unicode as u8,
+            self.info.stretch_h,
+            self.info.smooth as u8,
+            self.info.aa,
+            self.info.padding_up, self.info.padding_right, self.info.padding_down, self.info.padding_left,
+            self.info.spacing_horiz, self.info.spacing_vert,
+            self.info.outline,
+            self.info.fixed_height as u8,
+        ));
+
+        out.push_str(&format!(
+            "common lineHeight={} base={} scaleW={} scaleH={} pages={} packed={} alphaChnl={} redChnl={} greenChnl={} blueChnl={}\n",
+            self.common.line_height,
+            self.common.base,
+            self.common.scale_w,
+            self.common.scale_h,
+            self.common.pages,
+            self.common.packed as u8,
+            self.common.alpha_chnl,
+            self.common.red_chnl,
+            self.common.green_chnl,
+            self.common.blue_chnl,
+        ));
+
+        for (id, file) in self.pages.iter().enumerate() {
+            out.push_str(&format!("page id={} file=\"{}\"\n", id, file));
+        }
+
+        let mut char_ids: Vec<&u32> = self.chars.keys().collect();
+        char_ids.sort();
+
+        out.push_str(&format!("chars count={}\n", char_ids.len()));
+        for id in char_ids {
+            let c = &self.chars[id];
+            out.push_str(&format!(
+                "char id={} x={} y={} width={} height={} xoffset={} yoffset={} xadvance={} page={} chnl={}\n",
+                c.id, c.x, c.y, c.width, c.height, c.xoffset, c.yoffset, c.xadvance, c.page, c.chnl,
+            ));
+        }
+
+        if let Some(kernings) = &self.kernings {
+            out.push_str(&format!("kernings count={}\n", kernings.len()));
+            for k in kernings {
+                out.push_str(&format!("kerning first={} second={} amount={}\n", k.first, k.second, k.amount));
+            }
+        }
+
+        out
+    }
 }
 
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct Info {
     pub font_size: i16,
     pub smooth: bool,
@@ -250,7 +794,7 @@ pub struct Info {
 }
 
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct Common {
     pub line_height: u16,
     pub base: u16,
@@ -265,7 +809,7 @@ pub struct Common {
 }
 
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct Char {
     pub id: u32,
     pub x: u16,
@@ -280,9 +824,198 @@ pub struct Char {
 }
 
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct Kerning {
     pub first: u32,
     pub second: u32,
     pub amount: i16,
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_TEXT: &str = r#"
+info face="Arial" size=32 bold=1 italic=0 charset="" unicode=1 stretchH=100 smooth=1 aa=1 padding=1,2,3,4 spacing=5,6 outline=0 fixedHeight=0
+common lineHeight=38 base=30 scaleW=256 scaleH=256 pages=1 packed=0 alphaChnl=1 redChnl=0 greenChnl=0 blueChnl=0
+page id=0 file="font_0.png"
+chars count=2
+char id=32 x=0 y=0 width=1 height=1 xoffset=0 yoffset=27 xadvance=9 page=0 chnl=0
+char id=65 x=10 y=0 width=12 height=14 xoffset=1 yoffset=2 xadvance=13 page=0 chnl=0
+kernings count=1
+kerning first=65 second=66 amount=-2
+"#;
+
+    #[test]
+    fn binary_round_trip() {
+        let original = BMFont::from_text(SAMPLE_TEXT).unwrap();
+        let round_tripped = parse_bin(&original.to_bin()).unwrap();
+        assert_eq!(original, round_tripped);
+    }
+
+    // Hardens binary_round_trip with negative glyph offsets and more than
+    // one page, since SAMPLE_TEXT's single page and all-positive offsets
+    // wouldn't have caught a sign- or count-handling bug in to_bin/parse_bin.
+    #[test]
+    fn binary_round_trip_with_negative_offsets_and_multiple_pages() {
+        const TEXT: &str = r#"
+info face="Arial" size=32 bold=1 italic=0 charset="" unicode=1 stretchH=100 smooth=1 aa=1 padding=1,2,3,4 spacing=5,6 outline=0 fixedHeight=0
+common lineHeight=38 base=30 scaleW=256 scaleH=256 pages=2 packed=0 alphaChnl=1 redChnl=0 greenChnl=0 blueChnl=0
+page id=0 file="font_0.png"
+page id=1 file="font_1.png"
+chars count=2
+char id=32 x=0 y=0 width=1 height=1 xoffset=-3 yoffset=27 xadvance=9 page=0 chnl=0
+char id=65 x=10 y=0 width=12 height=14 xoffset=1 yoffset=-5 xadvance=13 page=1 chnl=0
+kernings count=1
+kerning first=65 second=66 amount=-2
+"#;
+        let original = BMFont::from_text(TEXT).unwrap();
+        let round_tripped = parse_bin(&original.to_bin()).unwrap();
+        assert_eq!(original, round_tripped);
+    }
+
+    #[test]
+    fn text_round_trip_preserves_charset() {
+        let mut original = BMFont::from_text(SAMPLE_TEXT).unwrap();
+        original.info.charset = 42;
+
+        let round_tripped = BMFont::from_text(&original.to_text()).unwrap();
+        assert_eq!(original, round_tripped);
+    }
+
+    // Hand-assembled binary file (not routed through `BMFont::to_bin`) so
+    // this exercises `parse_bin`'s own record-size accounting in isolation.
+    // A single Kerning record is 10 bytes on the wire; `size_of::<Kerning>()`
+    // is 12 due to alignment padding, so validating against the native size
+    // instead of KERNING_RECORD_SIZE rejected every file with exactly one
+    // kerning pair.
+    #[test]
+    fn parses_binary_file_with_kernings_block() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"BMF\x03");
+
+        let mut info = Vec::new();
+        info.write_i16_le(32);
+        info.push(0);
+        info.push(0);
+        info.write_u16_le(100);
+        info.push(1);
+        info.extend_from_slice(&[0, 0, 0, 0]);
+        info.extend_from_slice(&[0, 0]);
+        info.push(0);
+        info.write_cstring("Arial");
+        write_block(&mut bytes, 1, &info);
+
+        let mut common = Vec::new();
+        common.write_u16_le(38);
+        common.write_u16_le(30);
+        common.write_u16_le(256);
+        common.write_u16_le(256);
+        common.write_u16_le(0);
+        common.extend_from_slice(&[0, 0, 0, 0, 0]);
+        write_block(&mut bytes, 2, &common);
+
+        write_block(&mut bytes, 3, &[]);
+
+        let mut chars = Vec::new();
+        chars.write_u32_le(65);
+        chars.write_u16_le(10);
+        chars.write_u16_le(0);
+        chars.write_u16_le(12);
+        chars.write_u16_le(14);
+        chars.write_i16_le(1);
+        chars.write_i16_le(2);
+        chars.write_i16_le(13);
+        chars.push(0);
+        chars.push(0);
+        write_block(&mut bytes, 4, &chars);
+
+        let mut kernings = Vec::new();
+        kernings.write_u32_le(65);
+        kernings.write_u32_le(66);
+        kernings.write_i16_le(-2);
+        write_block(&mut bytes, 5, &kernings);
+
+        let font = parse_bin(&bytes).unwrap();
+        let pairs = font.kernings.as_ref().unwrap();
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(
+            pairs[0],
+            Kerning {
+                first: 65,
+                second: 66,
+                amount: -2,
+            }
+        );
+    }
+
+    #[test]
+    fn bin_read_decodes_little_endian() {
+        let mut cursor = Cursor::new(&[0x34u8, 0x12][..]);
+        assert_eq!(cursor.read_u16_le().unwrap(), 0x1234);
+
+        let mut cursor = Cursor::new(&[0xFFu8, 0xFF][..]);
+        assert_eq!(cursor.read_i16_le().unwrap(), -1);
+
+        let mut cursor = Cursor::new(&[0x78u8, 0x56, 0x34, 0x12][..]);
+        assert_eq!(cursor.read_u32_le().unwrap(), 0x1234_5678);
+    }
+
+    #[test]
+    fn bin_read_rejects_truncated_input() {
+        let mut cursor = Cursor::new(&[0x01u8][..]);
+        assert!(cursor.read_u16_le().is_err());
+    }
+
+    #[test]
+    fn try_read_u32_le_returns_none_on_short_input() {
+        let mut cursor = Cursor::new(&[0x01u8, 0x00][..]);
+        assert_eq!(cursor.try_read_u32_le(), None);
+    }
+
+    #[test]
+    fn tokenize_attrs_handles_quoted_and_unquoted_values() {
+        let attrs = tokenize_attrs(r#"face="Some Font" size=32 bold=0"#);
+        assert_eq!(attrs.get("face").map(String::as_str), Some("Some Font"));
+        assert_eq!(attrs.get("size").map(String::as_str), Some("32"));
+        assert_eq!(attrs.get("bold").map(String::as_str), Some("0"));
+    }
+
+    // Regression test for f19af5d: casting bytes to `char` misread UTF-8
+    // continuation bytes as whitespace and then sliced off a char boundary,
+    // panicking on non-ASCII unquoted values.
+    #[test]
+    fn tokenize_attrs_handles_non_ascii_unquoted_values() {
+        let attrs = tokenize_attrs("face=xĠfont size=32");
+        assert_eq!(attrs.get("face").map(String::as_str), Some("xĠfont"));
+        assert_eq!(attrs.get("size").map(String::as_str), Some("32"));
+    }
+
+    #[test]
+    fn str_to_chars_is_unicode_aware_and_non_panicking() {
+        let font = BMFont::from_text(SAMPLE_TEXT).unwrap();
+
+        let glyphs = font.str_to_chars("A\u{1F600}");
+        assert_eq!(glyphs.len(), 2);
+        assert_eq!(glyphs[0].map(|c| c.id), Some(65));
+        assert!(glyphs[1].is_none());
+    }
+
+    #[test]
+    fn kerning_looks_up_known_pairs_and_defaults_to_zero() {
+        let font = BMFont::from_text(SAMPLE_TEXT).unwrap();
+
+        assert_eq!(font.kerning(65, 66), -2);
+        assert_eq!(font.kerning(65, 32), 0);
+    }
+
+    #[test]
+    fn layout_width_sums_xadvance_and_kerning() {
+        let font = BMFont::from_text(SAMPLE_TEXT).unwrap();
+
+        assert_eq!(font.layout_width("A"), 13);
+        assert_eq!(font.layout_width("A "), 13 + 9);
+        assert_eq!(font.layout_width("\u{1F600}"), 0);
+    }
+}